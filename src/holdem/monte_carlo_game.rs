@@ -1,41 +1,185 @@
 use crate::core::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+// NOTE: this feature gate requires `serde` to be declared as an optional
+// dependency and `serde_json` as a dev-dependency under a `serde` feature
+// in Cargo.toml. This source tree has no Cargo.toml to edit; wire up the
+// feature there when this crate is assembled into a full workspace.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Aggregated win/tie/equity share for a single seat across many
+/// simulated showdowns.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Equity {
+    /// Number of trials this seat won outright.
+    pub wins: usize,
+    /// Number of trials this seat split the pot with at least one other seat.
+    pub ties: usize,
+    /// Fractional equity share accumulated over all trials, already
+    /// divided by the number of trials (sampled iterations, or enumerated
+    /// board combinations when `exact_equity()` is used).
+    pub equity: f64,
+}
+
+/// A single undealt card that would make a seat the sole best hand, along
+/// with the rank it would make.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Out {
+    /// The card that improves the seat's hand.
+    pub card: Card,
+    /// The rank the seat's hand would have once this card falls.
+    pub rank: Rank,
+}
+
+/// Above this many remaining board combinations, `equity()` falls back to
+/// Monte Carlo sampling instead of exhaustively enumerating them.
+const EXACT_EQUITY_COMBINATION_THRESHOLD: usize = 2_000;
+
+/// Which card-removal rules to build the deck with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeckVariant {
+    /// The full 52-card deck.
+    #[default]
+    Standard,
+    /// Short-deck (6+) hold'em: the 2s through 5s are removed, and a
+    /// flush outranks a full house to account for the thinner deck.
+    ShortDeck,
+}
+
+/// A fully-described outcome of one simulated showdown: the seats' hole
+/// cards, the final board, and each seat's resulting rank. Useful for
+/// dumping batches of simulated hands to JSON for analysis outside this
+/// crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationResult {
+    /// Each seat's hole cards plus any board cards already dealt into it.
+    pub hands: Vec<Hand>,
+    /// The final board.
+    pub board: Vec<Card>,
+    /// Each seat's resulting rank, indexed the same way as `hands`.
+    pub ranks: Vec<Rank>,
+}
 
 /// Current state of a game.
+///
+/// Not `Serialize`/`Deserialize`, even under the `serde` feature: it embeds
+/// `Card` and `Hand`. Use `simulate_detailed()` to get a `SimulationResult`
+/// snapshot instead if you need to hand outcomes to external tooling.
 #[derive(Debug)]
 pub struct MonteCarloGame {
-    /// Flatten deck
-    deck: FlatDeck,
+    /// Remaining cards to deal, in draw order.
+    deck: Vec<Card>,
     /// Community cards.
     board: Vec<Card>,
     /// Hands still playing.
     hands: Vec<Hand>,
     current_offset: usize,
+    /// Random number generator used to shuffle the deck between runs.
+    rng: StdRng,
+    /// Which deck composition and hand ranking rules are in effect.
+    variant: DeckVariant,
 }
 
 impl MonteCarloGame {
     /// If we already have hands then lets start there.
     pub fn new_with_hands(hands: Vec<Hand>) -> Result<Self, String> {
-        let mut d = Deck::default();
-        for h in &hands {
-            if h.len() != 2 {
-                return Err(String::from("Hand passed in doesn't have 2 cards."));
-            }
-            for c in h.iter() {
-                if !d.remove(*c) {
-                    return Err(format!("Card {} was already removed from the deck.", c));
-                }
-            }
-        }
-        Ok(Self {
-            deck: d.flatten(),
+        Self::build(hands, vec![], StdRng::from_entropy(), DeckVariant::Standard)
+    }
+
+    /// Like `new_with_hands`, but shuffles and deals using an RNG seeded
+    /// from `seed` so that repeated runs with the same inputs produce
+    /// identical board fill-outs.
+    pub fn new_with_hands_seeded(hands: Vec<Hand>, seed: u64) -> Result<Self, String> {
+        Self::build(
             hands,
-            board: vec![],
-            current_offset: 52,
-        })
+            vec![],
+            StdRng::seed_from_u64(seed),
+            DeckVariant::Standard,
+        )
+    }
+
+    /// Like `new_with_hands`, but builds the deck and ranks hands
+    /// according to `variant` (for example, short-deck hold'em).
+    pub fn new_with_hands_and_variant(
+        hands: Vec<Hand>,
+        variant: DeckVariant,
+    ) -> Result<Self, String> {
+        Self::build(hands, vec![], StdRng::from_entropy(), variant)
+    }
+
+    /// Like `new_with_hands`, but combines `new_with_hands_seeded`'s
+    /// deterministic RNG with `new_with_hands_and_variant`'s deck
+    /// composition, so short-deck (or other variant) simulations can also
+    /// be replayed exactly.
+    pub fn new_with_hands_seeded_and_variant(
+        hands: Vec<Hand>,
+        seed: u64,
+        variant: DeckVariant,
+    ) -> Result<Self, String> {
+        Self::build(hands, vec![], StdRng::seed_from_u64(seed), variant)
     }
 
     pub fn new_with_board(hands: Vec<Hand>, board: Vec<Card>) -> Result<Self, String> {
+        Self::build(hands, board, StdRng::from_entropy(), DeckVariant::Standard)
+    }
+
+    /// Like `new_with_board`, but shuffles and deals using an RNG seeded
+    /// from `seed` so that repeated runs with the same inputs produce
+    /// identical board fill-outs.
+    pub fn new_with_board_seeded(
+        hands: Vec<Hand>,
+        board: Vec<Card>,
+        seed: u64,
+    ) -> Result<Self, String> {
+        Self::build(
+            hands,
+            board,
+            StdRng::seed_from_u64(seed),
+            DeckVariant::Standard,
+        )
+    }
+
+    /// Like `new_with_board`, but builds the deck and ranks hands
+    /// according to `variant` (for example, short-deck hold'em).
+    pub fn new_with_board_and_variant(
+        hands: Vec<Hand>,
+        board: Vec<Card>,
+        variant: DeckVariant,
+    ) -> Result<Self, String> {
+        Self::build(hands, board, StdRng::from_entropy(), variant)
+    }
+
+    /// Like `new_with_board`, but combines `new_with_board_seeded`'s
+    /// deterministic RNG with `new_with_board_and_variant`'s deck
+    /// composition, so short-deck (or other variant) simulations can also
+    /// be replayed exactly.
+    pub fn new_with_board_seeded_and_variant(
+        hands: Vec<Hand>,
+        board: Vec<Card>,
+        seed: u64,
+        variant: DeckVariant,
+    ) -> Result<Self, String> {
+        Self::build(hands, board, StdRng::seed_from_u64(seed), variant)
+    }
+
+    fn build(
+        hands: Vec<Hand>,
+        board: Vec<Card>,
+        rng: StdRng,
+        variant: DeckVariant,
+    ) -> Result<Self, String> {
         let mut deck = Deck::default();
+        if variant == DeckVariant::ShortDeck {
+            for value in [Value::Two, Value::Three, Value::Four, Value::Five] {
+                for suit in [Suit::Spade, Suit::Club, Suit::Heart, Suit::Diamond] {
+                    deck.remove(Card { value, suit });
+                }
+            }
+        }
         if board.len() > 5 {
             return Err(String::from("Board passed in has more than 5 cards"));
         }
@@ -57,11 +201,20 @@ impl MonteCarloGame {
             }
         }
 
+        // `Deck`'s `flatten()` order depends on `HashSet` iteration order,
+        // which varies between otherwise identical `Deck`s. Sort into a
+        // canonical order before seeding the shuffle so the same
+        // hands/board/seed always produce the same deal.
+        let flat = deck.flatten();
+        let mut deck: Vec<Card> = flat[0..flat.len()].to_vec();
+        deck.sort();
         Ok(Self {
-            deck: deck.flatten(),
+            deck,
             hands,
             board,
             current_offset: 52,
+            rng,
+            variant,
         })
     }
 
@@ -70,6 +223,113 @@ impl MonteCarloGame {
     /// This will fill out the board and then return the tuple
     /// of which hand had the best rank in end.
     pub fn simulate(&mut self) -> Result<(usize, Rank), String> {
+        let (_, ranks) = self.fill_board_and_rank()?;
+        ranks
+            .into_iter()
+            .enumerate()
+            .max_by_key(|&(_, ref rank)| rank.clone())
+            .ok_or_else(|| String::from("Unable to determine best rank."))
+    }
+
+    /// Like `simulate()`, but returns the full `SimulationResult` rather
+    /// than just the winning seat.
+    pub fn simulate_detailed(&mut self) -> Result<SimulationResult, String> {
+        let (dealt, ranks) = self.fill_board_and_rank()?;
+        let mut board = self.board.clone();
+        board.extend(dealt);
+        Ok(SimulationResult {
+            hands: self.hands.clone(),
+            board,
+            ranks,
+        })
+    }
+
+    /// Run `iterations` independent simulations and aggregate the
+    /// win/tie/equity share for every seat, splitting ties evenly.
+    ///
+    /// Delegates to `exact_equity()` instead when few enough board cards
+    /// remain to enumerate every outcome exactly.
+    pub fn equity(&mut self, iterations: usize) -> Result<Vec<Equity>, String> {
+        if self.hands.is_empty() {
+            return Err(String::from("There are no hands."));
+        }
+        if iterations == 0 {
+            return Err(String::from("There must be at least one iteration."));
+        }
+        let num_cards = 5 - self.board.len();
+        if num_cards == 0 {
+            // The board is already complete, so there's only one possible
+            // outcome to score; weight it by `iterations` instead of
+            // recomputing the same showdown over and over.
+            return self.showdown_equity(iterations);
+        }
+        if n_choose_k(self.deck.len(), num_cards) <= EXACT_EQUITY_COMBINATION_THRESHOLD {
+            return self.exact_equity();
+        }
+        let mut equities = vec![Equity::default(); self.hands.len()];
+        for _ in 0..iterations {
+            let (_, ranks) = self.fill_board_and_rank()?;
+            let best_rank = ranks
+                .iter()
+                .cloned()
+                .max()
+                .ok_or_else(|| String::from("Unable to determine best rank."))?;
+            let winners: Vec<usize> = ranks
+                .iter()
+                .enumerate()
+                .filter(|&(_, rank)| *rank == best_rank)
+                .map(|(seat, _)| seat)
+                .collect();
+            let share = 1.0 / winners.len() as f64;
+            for &seat in &winners {
+                if winners.len() > 1 {
+                    equities[seat].ties += 1;
+                } else {
+                    equities[seat].wins += 1;
+                }
+                equities[seat].equity += share;
+            }
+            self.reset();
+        }
+        for equity in &mut equities {
+            equity.equity /= iterations as f64;
+        }
+        Ok(equities)
+    }
+
+    /// Score the single showdown the already-complete board produces,
+    /// weighted as if it had been observed `iterations` times.
+    fn showdown_equity(&mut self, iterations: usize) -> Result<Vec<Equity>, String> {
+        let (_, ranks) = self.fill_board_and_rank()?;
+        self.reset();
+        let best_rank = ranks
+            .iter()
+            .cloned()
+            .max()
+            .ok_or_else(|| String::from("Unable to determine best rank."))?;
+        let winners: Vec<usize> = ranks
+            .iter()
+            .enumerate()
+            .filter(|&(_, rank)| *rank == best_rank)
+            .map(|(seat, _)| seat)
+            .collect();
+        let share = 1.0 / winners.len() as f64;
+
+        let mut equities = vec![Equity::default(); self.hands.len()];
+        for &seat in &winners {
+            if winners.len() > 1 {
+                equities[seat].ties = iterations;
+            } else {
+                equities[seat].wins = iterations;
+            }
+            equities[seat].equity = share;
+        }
+        Ok(equities)
+    }
+
+    /// Fill out the board for every hand still in play and return the
+    /// cards dealt along with each hand's resulting rank.
+    fn fill_board_and_rank(&mut self) -> Result<(Vec<Card>, Vec<Rank>), String> {
         if self.hands.is_empty() {
             return Err(String::from("There are no hands."));
         }
@@ -83,22 +343,159 @@ impl MonteCarloGame {
         let num_cards = 5 - self.board.len();
         // Now iterate over a sample of the deck.
         self.shuffle_if_needed();
-        for c in &self.deck[self.current_offset..self.current_offset + num_cards] {
+        let dealt: Vec<Card> = self.deck[self.current_offset..self.current_offset + num_cards]
+            .to_vec();
+        for c in &dealt {
             for h in &mut self.hands {
                 h.push(*c);
             }
         }
         self.current_offset += num_cards;
 
-        // Now get the best rank of all the possible hands.
-        let best_rank = self
+        let ranks = self.hands.iter().map(|h| self.rank_for_variant(h)).collect();
+        Ok((dealt, ranks))
+    }
+
+    /// Compute exact equity by enumerating every remaining combination of
+    /// board cards, rather than sampling them.
+    pub fn exact_equity(&mut self) -> Result<Vec<Equity>, String> {
+        if self.hands.is_empty() {
+            return Err(String::from("There are no hands."));
+        }
+        let num_cards = 5 - self.board.len();
+        let remaining: Vec<Card> = self.deck.clone();
+        if n_choose_k(remaining.len(), num_cards) == 0 {
+            return Err(String::from(
+                "There are no reachable board combinations left to enumerate.",
+            ));
+        }
+        let mut equities = vec![Equity::default(); self.hands.len()];
+        let mut combinations = 0usize;
+
+        for combo in combinations_of(&remaining, num_cards) {
+            combinations += 1;
+            let ranks: Vec<Rank> = self
+                .hands
+                .iter()
+                .map(|h| {
+                    let mut h = h.clone();
+                    for c in &self.board {
+                        h.push(*c);
+                    }
+                    for c in &combo {
+                        h.push(*c);
+                    }
+                    self.rank_for_variant(&h)
+                })
+                .collect();
+            let best_rank = ranks
+                .iter()
+                .cloned()
+                .max()
+                .ok_or_else(|| String::from("Unable to determine best rank."))?;
+            let winners: Vec<usize> = ranks
+                .iter()
+                .enumerate()
+                .filter(|&(_, rank)| *rank == best_rank)
+                .map(|(seat, _)| seat)
+                .collect();
+            let share = 1.0 / winners.len() as f64;
+            for &seat in &winners {
+                if winners.len() > 1 {
+                    equities[seat].ties += 1;
+                } else {
+                    equities[seat].wins += 1;
+                }
+                equities[seat].equity += share;
+            }
+        }
+
+        for equity in &mut equities {
+            equity.equity /= combinations as f64;
+        }
+        Ok(equities)
+    }
+
+    /// Find the undealt cards that would turn each seat into the sole
+    /// best hand, including cards that break a current chop in a seat's
+    /// favor.
+    ///
+    /// The board must already have 3 or 4 cards (a flop or a turn). For
+    /// every remaining card in `deck`, this temporarily adds it to every
+    /// hand, re-ranks them, and records the card against any seat that
+    /// becomes the unique leader as a result, provided that seat wasn't
+    /// already the sole leader.
+    pub fn outs(&self) -> Result<Vec<Vec<Out>>, String> {
+        if self.hands.is_empty() {
+            return Err(String::from("There are no hands."));
+        }
+        if self.board.len() != 3 && self.board.len() != 4 {
+            return Err(String::from(
+                "Outs can only be calculated with a flop or turn board.",
+            ));
+        }
+
+        let current_ranks: Vec<Rank> = self
             .hands
             .iter()
-            .map(|h| h.rank())
-            .enumerate()
-            .max_by_key(|&(_, ref rank)| rank.clone())
-            .ok_or_else(|| String::from("Unable to determine best rank."));
-        Ok(best_rank?)
+            .map(|h| {
+                let mut h = h.clone();
+                for c in &self.board {
+                    h.push(*c);
+                }
+                self.rank_for_variant(&h)
+            })
+            .collect();
+        let current_best = current_ranks
+            .iter()
+            .cloned()
+            .max()
+            .ok_or_else(|| String::from("Unable to determine best rank."))?;
+        // A seat tied for the current best rank is only excluded from
+        // getting outs credit if it's the *sole* current leader: a seat
+        // that's merely sharing the current best rank (a chop) can still
+        // gain an out by breaking the tie in its own favor.
+        let tied_for_best = current_ranks.iter().filter(|rank| **rank == current_best).count();
+        let currently_sole_leader: Vec<bool> = current_ranks
+            .iter()
+            .map(|rank| tied_for_best == 1 && *rank == current_best)
+            .collect();
+
+        let mut outs = vec![Vec::new(); self.hands.len()];
+        for &card in &self.deck {
+            let ranks: Vec<Rank> = self
+                .hands
+                .iter()
+                .map(|h| {
+                    let mut h = h.clone();
+                    for c in &self.board {
+                        h.push(*c);
+                    }
+                    h.push(card);
+                    self.rank_for_variant(&h)
+                })
+                .collect();
+            let best_rank = ranks
+                .iter()
+                .cloned()
+                .max()
+                .ok_or_else(|| String::from("Unable to determine best rank."))?;
+            let leaders: Vec<usize> = ranks
+                .iter()
+                .enumerate()
+                .filter(|&(_, rank)| *rank == best_rank)
+                .map(|(seat, _)| seat)
+                .collect();
+            if let [seat] = leaders[..] {
+                if !currently_sole_leader[seat] {
+                    outs[seat].push(Out {
+                        card,
+                        rank: ranks[seat].clone(),
+                    });
+                }
+            }
+        }
+        Ok(outs)
     }
     /// Reset the game state.
     pub fn reset(&mut self) {
@@ -109,8 +506,224 @@ impl MonteCarloGame {
     fn shuffle_if_needed(&mut self) {
         if self.current_offset + 5 > self.deck.len() {
             self.current_offset = 0;
-            self.deck.shuffle();
+            let cards: &mut [Card] = &mut self.deck;
+            cards.shuffle(&mut self.rng);
+        }
+    }
+
+    /// Rank a hand according to `self.variant`. Short-deck swaps flush
+    /// and full house, since the thinner deck makes flushes harder to
+    /// make than full houses.
+    fn rank_for_variant(&self, hand: &Hand) -> Rank {
+        let rank = hand.rank();
+        match (self.variant, rank) {
+            (DeckVariant::ShortDeck, Rank::Flush(value)) => Rank::FullHouse(value),
+            (DeckVariant::ShortDeck, Rank::FullHouse(value)) => Rank::Flush(value),
+            (_, rank) => rank,
+        }
+    }
+}
+
+/// Number of ways to choose `k` items from `n`, saturating at `usize::MAX`
+/// rather than overflowing for large `n`.
+fn n_choose_k(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+    }
+    result.min(usize::MAX as u128) as usize
+}
+
+/// Enumerate every `k`-card combination of `cards`, in the order they
+/// appear in the slice.
+fn combinations_of(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if k > cards.len() {
+        return vec![];
+    }
+    let mut combos = Vec::new();
+    let mut indices: Vec<usize> = (0..k).collect();
+    loop {
+        combos.push(indices.iter().map(|&i| cards[i]).collect());
+
+        let mut i = k;
+        loop {
+            if i == 0 {
+                return combos;
+            }
+            i -= 1;
+            if indices[i] != i + cards.len() - k {
+                break;
+            }
+        }
+        indices[i] += 1;
+        for j in i + 1..k {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+}
+
+// `Card` and `Rank` have no serde support of their own in `core` at this
+// version, so `Out` and `SimulationResult` serialize through their own
+// manual impls here, going through
+// `Card`'s string form (e.g. "Ad") and a tagged `(name, value)` pair for
+// `Rank`, the same way `Hand::new_from_str` already parses cards.
+#[cfg(feature = "serde")]
+fn card_to_string(card: &Card) -> String {
+    card.to_string()
+}
+
+#[cfg(feature = "serde")]
+fn card_from_str(s: &str) -> Result<Card, String> {
+    let mut chars = s.chars();
+    let value = chars
+        .next()
+        .and_then(Value::from_char)
+        .ok_or_else(|| format!("Couldn't parse card value in {:?}", s))?;
+    let suit = chars
+        .next()
+        .and_then(Suit::from_char)
+        .ok_or_else(|| format!("Couldn't parse card suit in {:?}", s))?;
+    if chars.next().is_some() {
+        return Err(format!("Unexpected extra characters in card {:?}", s));
+    }
+    Ok(Card { value, suit })
+}
+
+#[cfg(feature = "serde")]
+fn rank_to_wire(rank: &Rank) -> (String, u32) {
+    let (name, value) = match *rank {
+        Rank::HighCard(v) => ("HighCard", v),
+        Rank::OnePair(v) => ("OnePair", v),
+        Rank::TwoPair(v) => ("TwoPair", v),
+        Rank::ThreeOfAKind(v) => ("ThreeOfAKind", v),
+        Rank::Straight(v) => ("Straight", v),
+        Rank::Flush(v) => ("Flush", v),
+        Rank::FullHouse(v) => ("FullHouse", v),
+        Rank::FourOfAKind(v) => ("FourOfAKind", v),
+        Rank::StraightFlush(v) => ("StraightFlush", v),
+    };
+    (name.to_string(), value)
+}
+
+#[cfg(feature = "serde")]
+fn rank_from_wire(name: &str, value: u32) -> Result<Rank, String> {
+    match name {
+        "HighCard" => Ok(Rank::HighCard(value)),
+        "OnePair" => Ok(Rank::OnePair(value)),
+        "TwoPair" => Ok(Rank::TwoPair(value)),
+        "ThreeOfAKind" => Ok(Rank::ThreeOfAKind(value)),
+        "Straight" => Ok(Rank::Straight(value)),
+        "Flush" => Ok(Rank::Flush(value)),
+        "FullHouse" => Ok(Rank::FullHouse(value)),
+        "FourOfAKind" => Ok(Rank::FourOfAKind(value)),
+        "StraightFlush" => Ok(Rank::StraightFlush(value)),
+        other => Err(format!("Unknown rank kind {:?}", other)),
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct OutWire {
+    card: String,
+    rank: (String, u32),
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Out {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        OutWire {
+            card: card_to_string(&self.card),
+            rank: rank_to_wire(&self.rank),
         }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Out {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let wire = OutWire::deserialize(deserializer)?;
+        Ok(Out {
+            card: card_from_str(&wire.card).map_err(serde::de::Error::custom)?,
+            rank: rank_from_wire(&wire.rank.0, wire.rank.1).map_err(serde::de::Error::custom)?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SimulationResultWire {
+    hands: Vec<Vec<String>>,
+    board: Vec<String>,
+    ranks: Vec<(String, u32)>,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SimulationResult {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        SimulationResultWire {
+            hands: self
+                .hands
+                .iter()
+                .map(|h| h.iter().map(card_to_string).collect())
+                .collect(),
+            board: self.board.iter().map(card_to_string).collect(),
+            ranks: self.ranks.iter().map(rank_to_wire).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SimulationResult {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let wire = SimulationResultWire::deserialize(deserializer)?;
+        let hands = wire
+            .hands
+            .iter()
+            .map(|cards| {
+                let cards: Result<Vec<Card>, String> =
+                    cards.iter().map(|s| card_from_str(s)).collect();
+                cards.map(Hand::new_with_cards)
+            })
+            .collect::<Result<Vec<Hand>, String>>()
+            .map_err(serde::de::Error::custom)?;
+        let board = wire
+            .board
+            .iter()
+            .map(|s| card_from_str(s))
+            .collect::<Result<Vec<Card>, String>>()
+            .map_err(serde::de::Error::custom)?;
+        let ranks = wire
+            .ranks
+            .iter()
+            .map(|(name, value)| rank_from_wire(name, *value))
+            .collect::<Result<Vec<Rank>, String>>()
+            .map_err(serde::de::Error::custom)?;
+        Ok(SimulationResult {
+            hands,
+            board,
+            ranks,
+        })
     }
 }
 
@@ -154,4 +767,395 @@ mod test {
         assert!(result.1 >= Rank::ThreeOfAKind(4));
 
     }
+
+    #[test]
+    fn test_equity_sums_to_one() {
+        let hands: Vec<Hand> = ["AdAh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let mut g = MonteCarloGame::new_with_hands(hands).unwrap();
+        let equities = g.equity(100).unwrap();
+        assert_eq!(2, equities.len());
+        let total: f64 = equities.iter().map(|e| e.equity).sum();
+        assert!((total - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_equity_detects_chop() {
+        let hands: Vec<Hand> = ["AdKh", "AhKd"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Two,
+                suit: Suit::Club,
+            },
+            Card {
+                value: Value::Seven,
+                suit: Suit::Diamond,
+            },
+            Card {
+                value: Value::Nine,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Jack,
+                suit: Suit::Heart,
+            },
+            Card {
+                value: Value::Queen,
+                suit: Suit::Club,
+            },
+        ];
+        let mut g = MonteCarloGame::new_with_board(hands, board).unwrap();
+        let equities = g.equity(10).unwrap();
+        assert_eq!(10, equities[0].ties);
+        assert_eq!(10, equities[1].ties);
+        assert_eq!(0, equities[0].wins);
+        assert_eq!(0, equities[1].wins);
+    }
+
+    #[test]
+    fn test_outs_flush_draw() {
+        // Seat 1 already has trip nines, so pairing an overcard wouldn't be
+        // enough for seat 0 to take the lead; only completing the flush
+        // does, which keeps this test isolated to flush outs.
+        let hands: Vec<Hand> = ["AsKs", "9h9c"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Three,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Seven,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Nine,
+                suit: Suit::Diamond,
+            },
+        ];
+        // Four spades between the hole cards and board, but not a flush
+        // yet, so every remaining spade except the 9s (which gives seat 1
+        // quads) should show up as an out for seat 0.
+        let g = MonteCarloGame::new_with_board(hands, board).unwrap();
+        let outs = g.outs().unwrap();
+        assert_eq!(2, outs.len());
+        assert!(outs[0]
+            .iter()
+            .all(|out| out.card.suit == Suit::Spade && out.rank >= Rank::Flush(0)));
+        assert!(!outs[0].is_empty());
+    }
+
+    #[test]
+    fn test_outs_credits_breaking_a_chop() {
+        // Seats share the same hole card values (Ace, King), so they're
+        // currently tied for best hand. Seat 0 is suited in diamonds and
+        // the board already shows two diamonds; any third diamond gives
+        // seat 0 a flush that seat 1 can't match, breaking the chop in
+        // seat 0's favor. That should count as an out even though seat 0
+        // was already sharing the lead before the card fell.
+        let hands: Vec<Hand> = ["AdKd", "AhKs"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Two,
+                suit: Suit::Diamond,
+            },
+            Card {
+                value: Value::Seven,
+                suit: Suit::Diamond,
+            },
+            Card {
+                value: Value::Nine,
+                suit: Suit::Club,
+            },
+        ];
+        let g = MonteCarloGame::new_with_board(hands, board).unwrap();
+        let outs = g.outs().unwrap();
+        assert_eq!(2, outs.len());
+        assert!(outs[0]
+            .iter()
+            .any(|out| out.card.suit == Suit::Diamond && out.rank >= Rank::Flush(0)));
+    }
+
+    #[test]
+    fn test_seeded_games_are_deterministic() {
+        let hands_a: Vec<Hand> = ["AdAh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let hands_b = hands_a.clone();
+        let mut game_a = MonteCarloGame::new_with_hands_seeded(hands_a, 42).unwrap();
+        let mut game_b = MonteCarloGame::new_with_hands_seeded(hands_b, 42).unwrap();
+        assert_eq!(game_a.simulate().unwrap(), game_b.simulate().unwrap());
+    }
+
+    #[test]
+    fn test_simulate_detailed() {
+        let hands: Vec<Hand> = ["AdAh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let mut g = MonteCarloGame::new_with_hands(hands).unwrap();
+        let result = g.simulate_detailed().unwrap();
+        assert_eq!(2, result.hands.len());
+        assert_eq!(5, result.board.len());
+        assert_eq!(2, result.ranks.len());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_equity_json_round_trip() {
+        let hands: Vec<Hand> = ["AdAh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let mut g = MonteCarloGame::new_with_hands(hands).unwrap();
+        let equities = g.equity(10).unwrap();
+        let json = serde_json::to_string(&equities).unwrap();
+        let round_tripped: Vec<Equity> = serde_json::from_str(&json).unwrap();
+        assert_eq!(equities, round_tripped);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_simulation_result_json_round_trip() {
+        let hands: Vec<Hand> = ["AdAh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let mut g = MonteCarloGame::new_with_hands(hands).unwrap();
+        let result = g.simulate_detailed().unwrap();
+        let json = serde_json::to_string(&result).unwrap();
+        let round_tripped: SimulationResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(result, round_tripped);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_out_json_round_trip() {
+        let hands: Vec<Hand> = ["AsKs", "9h9c"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Three,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Seven,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Nine,
+                suit: Suit::Diamond,
+            },
+        ];
+        let g = MonteCarloGame::new_with_board(hands, board).unwrap();
+        let outs = g.outs().unwrap();
+        let json = serde_json::to_string(&outs).unwrap();
+        let round_tripped: Vec<Vec<Out>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(outs, round_tripped);
+    }
+
+    #[test]
+    fn test_exact_equity_sums_to_one() {
+        let hands: Vec<Hand> = ["AdKh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Ace,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::King,
+                suit: Suit::Diamond,
+            },
+            Card {
+                value: Value::Nine,
+                suit: Suit::Club,
+            },
+            Card {
+                value: Value::Four,
+                suit: Suit::Heart,
+            },
+        ];
+        let mut g = MonteCarloGame::new_with_board(hands, board).unwrap();
+        let equities = g.exact_equity().unwrap();
+        assert_eq!(2, equities.len());
+        let total: f64 = equities.iter().map(|e| e.equity).sum();
+        assert!((total - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_exact_equity_merges_board_into_hand() {
+        // Board already makes quad twos once paired with seat 0's hole
+        // card, which no two-card runout can ever overcome. If
+        // `exact_equity()` forgot to merge `self.board` into the hands
+        // before ranking, it would be scoring 4-card pseudo-hands that
+        // don't include the quads at all, and seat 0 wouldn't win every
+        // combination.
+        let hands: Vec<Hand> = ["2hKd", "AsKs"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Two,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Two,
+                suit: Suit::Club,
+            },
+            Card {
+                value: Value::Two,
+                suit: Suit::Diamond,
+            },
+        ];
+        let mut g = MonteCarloGame::new_with_board(hands, board).unwrap();
+        let equities = g.exact_equity().unwrap();
+        assert_eq!(2, equities.len());
+        assert!((equities[0].equity - 1.0).abs() < 0.0001);
+        assert_eq!(0.0, equities[1].equity);
+    }
+
+    #[test]
+    fn test_equity_rejects_zero_iterations() {
+        let hands: Vec<Hand> = ["AdKh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let mut g = MonteCarloGame::new_with_hands(hands).unwrap();
+        assert!(g.equity(0).is_err());
+    }
+
+    #[test]
+    fn test_equity_delegates_to_exact_on_river() {
+        let hands: Vec<Hand> = ["AdKh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Ace,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::King,
+                suit: Suit::Diamond,
+            },
+            Card {
+                value: Value::Nine,
+                suit: Suit::Club,
+            },
+            Card {
+                value: Value::Four,
+                suit: Suit::Heart,
+            },
+        ];
+        // With a single card left, there are only a handful of outcomes,
+        // so equity() should route to exact_equity() regardless of the
+        // iteration count requested. Sampling wouldn't reliably reproduce
+        // exact_equity()'s output across different iteration counts, so
+        // an exact match here is only possible if dispatch happened.
+        let mut exact = MonteCarloGame::new_with_board(hands.clone(), board.clone()).unwrap();
+        let expected = exact.exact_equity().unwrap();
+
+        let mut small = MonteCarloGame::new_with_board(hands.clone(), board.clone()).unwrap();
+        assert_eq!(expected, small.equity(3).unwrap());
+
+        let mut large = MonteCarloGame::new_with_board(hands, board).unwrap();
+        assert_eq!(expected, large.equity(5_000).unwrap());
+    }
+
+    #[test]
+    fn test_n_choose_k() {
+        assert_eq!(1, n_choose_k(5, 0));
+        assert_eq!(5, n_choose_k(5, 1));
+        assert_eq!(10, n_choose_k(5, 2));
+        assert_eq!(0, n_choose_k(2, 5));
+    }
+
+    #[test]
+    fn test_short_deck_excludes_low_cards() {
+        let hands: Vec<Hand> = ["AdAh", "2c2s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        assert!(
+            MonteCarloGame::new_with_hands_and_variant(hands, DeckVariant::ShortDeck).is_err()
+        );
+    }
+
+    #[test]
+    fn test_seeded_short_deck_games_are_deterministic() {
+        let hands_a: Vec<Hand> = ["AsAh", "7c7s"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let hands_b = hands_a.clone();
+        let mut game_a = MonteCarloGame::new_with_hands_seeded_and_variant(
+            hands_a,
+            42,
+            DeckVariant::ShortDeck,
+        )
+        .unwrap();
+        let mut game_b = MonteCarloGame::new_with_hands_seeded_and_variant(
+            hands_b,
+            42,
+            DeckVariant::ShortDeck,
+        )
+        .unwrap();
+        assert_eq!(game_a.simulate().unwrap(), game_b.simulate().unwrap());
+    }
+
+    #[test]
+    fn test_short_deck_flush_beats_full_house() {
+        let hands: Vec<Hand> = ["AsKs", "7d7h"]
+            .iter()
+            .map(|s| Hand::new_from_str(s).unwrap())
+            .collect();
+        let board: Vec<Card> = vec![
+            Card {
+                value: Value::Nine,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Ten,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Jack,
+                suit: Suit::Spade,
+            },
+            Card {
+                value: Value::Seven,
+                suit: Suit::Club,
+            },
+            Card {
+                value: Value::Nine,
+                suit: Suit::Diamond,
+            },
+        ];
+        let mut g = MonteCarloGame::new_with_board_and_variant(
+            hands,
+            board,
+            DeckVariant::ShortDeck,
+        )
+        .unwrap();
+        let result = g.simulate().unwrap();
+        assert_eq!(0, result.0);
+    }
 }